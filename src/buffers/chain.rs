@@ -0,0 +1,212 @@
+use crate::buffers::{BufferError, Checkpoint, FBError, Int};
+use crate::Unstructured;
+
+/// A source of unstructured data assembled from several borrowed segments
+///
+/// This buffer presents an ordered list of `&[u8]` segments as a single
+/// logical source of unstructured data, reading across segment boundaries
+/// transparently. It's useful for feeding a fixed header or seed ahead of a
+/// fuzzer's mutable bytes, or for concatenating corpus fragments, without
+/// first copying them into one contiguous buffer. Once the final segment is
+/// exhausted it stays exhausted, same as [`FiniteBuffer`](crate::FiniteBuffer).
+pub struct ChainBuffer<'a> {
+    segments: Vec<&'a [u8]>,
+    offset: usize,
+    virtual_len: usize,
+    container_size_limit: usize,
+}
+
+impl<'a> ChainBuffer<'a> {
+    /// Create a new ChainBuffer from an ordered list of segments
+    pub fn new(segments: Vec<&'a [u8]>) -> Result<Self, BufferError> {
+        let total_len: usize = segments.iter().map(|s| s.len()).sum();
+        if total_len == 0 {
+            return Err(BufferError::EmptyInput);
+        }
+        Ok(ChainBuffer {
+            segments,
+            offset: 0,
+            virtual_len: total_len,
+            container_size_limit: total_len,
+        })
+    }
+
+    /// Set the non-default container size limit
+    pub fn container_size_limit(mut self, csl: usize) -> Self {
+        self.container_size_limit = csl;
+        self
+    }
+
+    fn total_len(&self) -> usize {
+        self.segments.iter().map(|s| s.len()).sum()
+    }
+
+    fn byte_at(&self, idx: usize) -> u8 {
+        let mut remaining = idx;
+        for seg in &self.segments {
+            if remaining < seg.len() {
+                return seg[remaining];
+            }
+            remaining -= seg.len();
+        }
+        unreachable!("ChainBuffer index out of bounds")
+    }
+
+    // A zero-copy slice is only possible when the requested run lives inside
+    // a single segment; a run spanning a segment boundary falls back to
+    // `None`/an error rather than copying.
+    fn borrowed_slice(&self, start: usize, n: usize) -> Option<&'a [u8]> {
+        let mut base = start;
+        for seg in &self.segments {
+            if base < seg.len() {
+                return if base + n <= seg.len() {
+                    Some(&seg[base..base + n])
+                } else {
+                    None
+                };
+            }
+            base -= seg.len();
+        }
+        None
+    }
+}
+
+impl<'a> Unstructured for ChainBuffer<'a> {
+    type Error = FBError;
+
+    fn fill_buffer(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if self.virtual_len.saturating_sub(self.offset) >= buffer.len() {
+            for (i, b) in buffer.iter_mut().enumerate() {
+                *b = self.byte_at(self.offset + i);
+            }
+            self.offset += buffer.len();
+            Ok(())
+        } else {
+            Err(FBError::InsufficientBytes)
+        }
+    }
+
+    fn container_size(&mut self) -> Result<usize, Self::Error> {
+        self.int_in_range(0, self.container_size_limit)
+    }
+
+    fn reset(&mut self) {
+        self.offset = 0;
+    }
+
+    fn shift_right(&mut self, total: usize) -> Result<(), Self::Error> {
+        if self.virtual_len.saturating_sub(self.offset) < total {
+            Err(FBError::InsufficientBytes)
+        } else {
+            self.offset += total;
+            Ok(())
+        }
+    }
+
+    fn shrink(&mut self) -> usize {
+        self.virtual_len /= 2;
+        self.virtual_len
+    }
+
+    fn int_in_range<T: Int>(&mut self, start: T, end: T) -> Result<T, Self::Error> {
+        let range: u64 = end.as_u64().wrapping_sub(start.as_u64()).wrapping_add(1);
+        let mut acc: u64 = 0;
+        let mut multiplier: u64 = 1;
+        while multiplier < range && self.virtual_len > self.offset {
+            self.virtual_len -= 1;
+            let byte = self.byte_at(self.virtual_len);
+            acc = (acc << 8) | u64::from(byte);
+            multiplier = multiplier.saturating_mul(256);
+        }
+        let result = if range == 0 { acc } else { acc % range };
+        Ok(T::from_u64(start.as_u64().wrapping_add(result)))
+    }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint::new(self.offset, 0, self.virtual_len)
+    }
+
+    fn restore(&mut self, cp: Checkpoint) {
+        self.offset = cp.offset;
+        self.virtual_len = cp.virtual_len;
+    }
+
+    fn set_virtual_len(&mut self, len: usize) {
+        self.virtual_len = len.min(self.total_len());
+    }
+
+    fn peek_bytes(&self, n: usize) -> Option<&[u8]> {
+        if self.virtual_len.saturating_sub(self.offset) < n {
+            return None;
+        }
+        self.borrowed_slice(self.offset, n)
+    }
+
+    fn take_bytes(&mut self, n: usize) -> Result<&'a [u8], Self::Error> {
+        if self.virtual_len.saturating_sub(self.offset) < n {
+            return Err(FBError::InsufficientBytes);
+        }
+        match self.borrowed_slice(self.offset, n) {
+            Some(bytes) => {
+                self.offset += n;
+                Ok(bytes)
+            }
+            None => Err(FBError::InsufficientBytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chain_buffer_fill_buffer_across_segments() {
+        let a = [1, 2];
+        let b = [3, 4, 5];
+        let mut cb = ChainBuffer::new(vec![&a[..], &b[..]]).unwrap();
+        let mut z = [0; 4];
+        cb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+        assert!(cb.fill_buffer(&mut z).is_err());
+    }
+
+    #[test]
+    fn chain_buffer_reset() {
+        let a = [1, 2];
+        let b = [3, 4, 5];
+        let mut cb = ChainBuffer::new(vec![&a[..], &b[..]]).unwrap();
+        let mut z = [0; 5];
+        cb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4, 5]);
+        cb.reset();
+        cb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn chain_buffer_take_bytes() {
+        let a = [1, 2];
+        let b = [3, 4, 5];
+        let mut cb = ChainBuffer::new(vec![&a[..], &b[..]]).unwrap();
+        assert_eq!(cb.take_bytes(2).unwrap(), &[1, 2]);
+        assert_eq!(cb.take_bytes(3).unwrap(), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn chain_buffer_take_bytes_spanning_segments_is_not_zero_copy() {
+        let a = [1, 2];
+        let b = [3, 4, 5];
+        let mut cb = ChainBuffer::new(vec![&a[..], &b[..]]).unwrap();
+        assert!(cb.take_bytes(3).is_err());
+        let mut z = [0; 3];
+        cb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3]);
+    }
+
+    #[test]
+    fn chain_buffer_empty_input() {
+        assert!(ChainBuffer::new(vec![]).is_err());
+        assert!(ChainBuffer::new(vec![&[][..], &[][..]]).is_err());
+    }
+}
@@ -1,5 +1,5 @@
-use crate::buffers::BufferError;
-use crate::{Arbitrary, Unstructured};
+use crate::buffers::{BufferError, Checkpoint, Int};
+use crate::Unstructured;
 
 /// A source of unstructured data with a finite size
 ///
@@ -10,6 +10,7 @@ pub struct FiniteBuffer<'a> {
     offset: usize,
     virtual_len: usize,
     container_size_limit: usize,
+    pad_with_zeros: bool,
 }
 
 impl<'a> FiniteBuffer<'a> {
@@ -24,6 +25,7 @@ impl<'a> FiniteBuffer<'a> {
             offset: 0,
             virtual_len: buffer.len(),
             container_size_limit: buffer.len(),
+            pad_with_zeros: false,
         })
     }
 
@@ -32,6 +34,19 @@ impl<'a> FiniteBuffer<'a> {
         self.container_size_limit = csl;
         self
     }
+
+    /// Enable non-failing exhaustion mode
+    ///
+    /// By default, once the underlying data is exhausted `fill_buffer` and
+    /// `shift_right` return `FBError::InsufficientBytes`. With this mode
+    /// enabled, `fill_buffer` instead copies whatever real bytes remain and
+    /// pads the rest of the caller's buffer with `0`, and `shift_right`
+    /// saturates at `virtual_len` instead of failing, so a single
+    /// `Arbitrary` impl can always produce some value.
+    pub fn pad_with_zeros(mut self) -> Self {
+        self.pad_with_zeros = true;
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -53,24 +68,51 @@ impl<'a> Unstructured for FiniteBuffer<'a> {
             }
             self.offset = max;
             Ok(())
+        } else if self.pad_with_zeros {
+            let remaining = self.virtual_len.saturating_sub(self.offset);
+            for (i, idx) in (self.offset..self.virtual_len).enumerate() {
+                buffer[i] = self.buffer[idx];
+            }
+            for b in &mut buffer[remaining..] {
+                *b = 0;
+            }
+            self.offset = self.virtual_len;
+            Ok(())
         } else {
             Err(FBError::InsufficientBytes)
         }
     }
 
-    // NOTE(blt) I'm not sure if this is the right definition. I don't
-    // understand the purpose of container_size.
     fn container_size(&mut self) -> Result<usize, Self::Error> {
-        <usize as Arbitrary>::arbitrary(self).map(|x| x % self.container_size_limit)
+        self.int_in_range(0, self.container_size_limit)
     }
 
     fn reset(&mut self) {
         self.offset = 0;
     }
 
+    fn int_in_range<T: Int>(&mut self, start: T, end: T) -> Result<T, Self::Error> {
+        let range: u64 = end.as_u64().wrapping_sub(start.as_u64()).wrapping_add(1);
+        let mut acc: u64 = 0;
+        let mut multiplier: u64 = 1;
+        while multiplier < range && self.virtual_len > self.offset {
+            self.virtual_len -= 1;
+            let byte = self.buffer[self.virtual_len];
+            acc = (acc << 8) | u64::from(byte);
+            multiplier = multiplier.saturating_mul(256);
+        }
+        let result = if range == 0 { acc } else { acc % range };
+        Ok(T::from_u64(start.as_u64().wrapping_add(result)))
+    }
+
     fn shift_right(&mut self, total: usize) -> Result<(), Self::Error> {
         if self.virtual_len.saturating_sub(self.offset) < total {
-            Err(FBError::InsufficientBytes)
+            if self.pad_with_zeros {
+                self.offset = self.virtual_len;
+                Ok(())
+            } else {
+                Err(FBError::InsufficientBytes)
+            }
         } else {
             self.offset += total;
             Ok(())
@@ -81,6 +123,37 @@ impl<'a> Unstructured for FiniteBuffer<'a> {
         self.virtual_len /= 2;
         self.virtual_len
     }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint::new(self.offset, 0, self.virtual_len)
+    }
+
+    fn restore(&mut self, cp: Checkpoint) {
+        self.offset = cp.offset;
+        self.virtual_len = cp.virtual_len;
+    }
+
+    fn set_virtual_len(&mut self, len: usize) {
+        self.virtual_len = len.min(self.buffer.len());
+    }
+
+    fn peek_bytes(&self, n: usize) -> Option<&[u8]> {
+        if self.virtual_len.saturating_sub(self.offset) >= n {
+            Some(&self.buffer[self.offset..self.offset + n])
+        } else {
+            None
+        }
+    }
+
+    fn take_bytes(&mut self, n: usize) -> Result<&'a [u8], Self::Error> {
+        if self.virtual_len.saturating_sub(self.offset) >= n {
+            let bytes = &self.buffer[self.offset..self.offset + n];
+            self.offset += n;
+            Ok(bytes)
+        } else {
+            Err(FBError::InsufficientBytes)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -127,4 +200,100 @@ mod test {
         assert_eq!(z, [1, 2]);
         assert!(rb.fill_buffer(&mut z).is_err());
     }
+
+    #[test]
+    fn finite_buffer_pad_with_zeros_fill_buffer() {
+        let x = [1, 2, 3, 4];
+        let mut rb = FiniteBuffer::new(&x).unwrap().pad_with_zeros();
+        let mut z = [0; 4];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [0, 0, 0, 0]);
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn finite_buffer_pad_with_zeros_partial_fill_buffer() {
+        let x = [1, 2, 3, 4];
+        let mut rb = FiniteBuffer::new(&x).unwrap().pad_with_zeros();
+        let mut z = [0; 3];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3]);
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [4, 0, 0]);
+    }
+
+    #[test]
+    fn finite_buffer_int_in_range() {
+        let x = [1, 2, 3, 4, 5, 6];
+        let mut rb = FiniteBuffer::new(&x).unwrap();
+        assert_eq!(rb.int_in_range(0u32, 11).unwrap(), 6);
+        assert_eq!(rb.int_in_range(0u32, 11).unwrap(), 5);
+        let mut z = [0; 4];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+        assert!(rb.fill_buffer(&mut [0; 1]).is_err());
+    }
+
+    #[test]
+    fn finite_buffer_container_size() {
+        let x = [1, 2, 3, 4, 5, 6];
+        let mut rb = FiniteBuffer::new(&x).unwrap().container_size_limit(11);
+        assert_eq!(rb.container_size().unwrap(), 6);
+        assert_eq!(rb.container_size().unwrap(), 5);
+    }
+
+    #[test]
+    fn finite_buffer_checkpoint_restore() {
+        let x = [1, 2, 3, 4];
+        let mut rb = FiniteBuffer::new(&x).unwrap();
+        let cp = rb.checkpoint();
+        let mut z = [0; 2];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2]);
+        rb.restore(cp);
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2]);
+    }
+
+    #[test]
+    fn finite_buffer_set_virtual_len() {
+        let x = [1, 2, 3, 4];
+        let mut rb = FiniteBuffer::new(&x).unwrap();
+        assert_eq!(2, rb.shrink());
+        rb.set_virtual_len(4);
+        let mut z = [0; 4];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn finite_buffer_peek_bytes() {
+        let x = [1, 2, 3, 4];
+        let rb = FiniteBuffer::new(&x).unwrap();
+        assert_eq!(rb.peek_bytes(2), Some(&[1, 2][..]));
+        assert_eq!(rb.peek_bytes(2), Some(&[1, 2][..]));
+        assert_eq!(rb.peek_bytes(5), None);
+    }
+
+    #[test]
+    fn finite_buffer_take_bytes() {
+        let x = [1, 2, 3, 4];
+        let mut rb = FiniteBuffer::new(&x).unwrap();
+        assert_eq!(rb.take_bytes(2).unwrap(), &[1, 2]);
+        assert_eq!(rb.take_bytes(2).unwrap(), &[3, 4]);
+        assert!(rb.take_bytes(1).is_err());
+    }
+
+    #[test]
+    fn finite_buffer_pad_with_zeros_shift_right() {
+        let x = [1, 2, 3, 4];
+        let mut rb = FiniteBuffer::new(&x).unwrap().pad_with_zeros();
+        assert!(rb.shift_right(x.len() + 1).is_ok());
+        let mut z = [0; 2];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [0, 0]);
+    }
 }
@@ -1,6 +1,8 @@
+mod chain;
 mod finite;
 mod ring;
 
+pub use self::chain::*;
 pub use self::finite::*;
 pub use self::ring::*;
 
@@ -13,3 +15,59 @@ pub enum BufferError {
     /// Shift operation failed for want of room to shift
     ShiftFailure,
 }
+
+/// Integer types that [`Unstructured::int_in_range`](crate::Unstructured::int_in_range)
+/// can produce
+///
+/// This is implemented for the built-in integer types up to 64 bits wide
+/// (`u128` and `i128` are not covered), allowing
+/// `int_in_range` to stay generic over the size and signedness of the value
+/// it draws.
+pub trait Int: Copy + PartialOrd {
+    /// Widen `self` to a `u64` for use as an accumulator
+    fn as_u64(self) -> u64;
+    /// Narrow a `u64` accumulator back down to `Self`
+    fn from_u64(x: u64) -> Self;
+}
+
+macro_rules! impl_int {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Int for $ty {
+                fn as_u64(self) -> u64 {
+                    self as u64
+                }
+
+                fn from_u64(x: u64) -> Self {
+                    x as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A saved position within a buffer's consumption state
+///
+/// Captured with [`Unstructured::checkpoint`](crate::Unstructured::checkpoint)
+/// and later restored with
+/// [`Unstructured::restore`](crate::Unstructured::restore). This lets a
+/// reducer try a smaller buffer length and roll back if it fails to
+/// reproduce, rather than only ever shrinking forward with no way back.
+#[derive(Debug, Clone, Copy)]
+pub struct Checkpoint {
+    offset: usize,
+    tail_offset: usize,
+    virtual_len: usize,
+}
+
+impl Checkpoint {
+    pub(crate) fn new(offset: usize, tail_offset: usize, virtual_len: usize) -> Self {
+        Checkpoint {
+            offset,
+            tail_offset,
+            virtual_len,
+        }
+    }
+}
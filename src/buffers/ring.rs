@@ -1,5 +1,5 @@
-use crate::buffers::BufferError;
-use crate::{Arbitrary, Unstructured};
+use crate::buffers::{BufferError, Checkpoint, Int};
+use crate::Unstructured;
 
 /// A source of unstructured data which returns the same data over and over again
 ///
@@ -8,6 +8,7 @@ use crate::{Arbitrary, Unstructured};
 pub struct RingBuffer<'a> {
     buffer: &'a [u8],
     offset: usize,
+    tail_offset: usize,
     virtual_len: usize,
     container_size_limit: usize,
 }
@@ -22,6 +23,7 @@ impl<'a> RingBuffer<'a> {
             buffer,
             virtual_len: buffer.len(),
             offset: 0,
+            tail_offset: 0,
             container_size_limit: buffer.len(),
         })
     }
@@ -49,11 +51,12 @@ impl<'a> Unstructured for RingBuffer<'a> {
     }
 
     fn container_size(&mut self) -> Result<usize, Self::Error> {
-        <usize as Arbitrary>::arbitrary(self).map(|x| x % self.container_size_limit)
+        self.int_in_range(0, self.container_size_limit)
     }
 
     fn reset(&mut self) {
         self.offset = 0;
+        self.tail_offset = 0;
         self.virtual_len = self.buffer.len();
     }
 
@@ -66,6 +69,54 @@ impl<'a> Unstructured for RingBuffer<'a> {
         self.virtual_len /= 2;
         self.virtual_len
     }
+
+    fn int_in_range<T: Int>(&mut self, start: T, end: T) -> Result<T, Self::Error> {
+        let range: u64 = end.as_u64().wrapping_sub(start.as_u64()).wrapping_add(1);
+        let mut acc: u64 = 0;
+        let mut multiplier: u64 = 1;
+        while multiplier < range {
+            let idx = (self.virtual_len - 1 + self.virtual_len - (self.tail_offset % self.virtual_len))
+                % self.virtual_len;
+            let byte = self.buffer[idx];
+            self.tail_offset += 1;
+            acc = (acc << 8) | u64::from(byte);
+            multiplier = multiplier.saturating_mul(256);
+        }
+        let result = if range == 0 { acc } else { acc % range };
+        Ok(T::from_u64(start.as_u64().wrapping_add(result)))
+    }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        Checkpoint::new(self.offset, self.tail_offset, self.virtual_len)
+    }
+
+    fn restore(&mut self, cp: Checkpoint) {
+        self.offset = cp.offset;
+        self.tail_offset = cp.tail_offset;
+        self.virtual_len = cp.virtual_len;
+    }
+
+    fn set_virtual_len(&mut self, len: usize) {
+        self.virtual_len = len.min(self.buffer.len()).max(1);
+    }
+
+    fn peek_bytes(&self, n: usize) -> Option<&[u8]> {
+        if self.offset + n <= self.virtual_len {
+            Some(&self.buffer[self.offset..self.offset + n])
+        } else {
+            None
+        }
+    }
+
+    fn take_bytes(&mut self, n: usize) -> Result<&'a [u8], Self::Error> {
+        if self.offset + n <= self.virtual_len {
+            let bytes = &self.buffer[self.offset..self.offset + n];
+            self.offset = (self.offset + n) % self.virtual_len;
+            Ok(bytes)
+        } else {
+            Err(())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -113,10 +164,61 @@ mod test {
     fn ring_buffer_container_size() {
         let x = [1, 2, 3, 4, 5];
         let mut rb = RingBuffer::new(&x).unwrap().container_size_limit(11);
-        assert_eq!(rb.container_size().unwrap(), 9);
-        assert_eq!(rb.container_size().unwrap(), 1);
+        assert_eq!(rb.container_size().unwrap(), 5);
+        assert_eq!(rb.container_size().unwrap(), 4);
+        assert_eq!(rb.container_size().unwrap(), 3);
         assert_eq!(rb.container_size().unwrap(), 2);
-        assert_eq!(rb.container_size().unwrap(), 6);
         assert_eq!(rb.container_size().unwrap(), 1);
     }
+
+    #[test]
+    fn ring_buffer_int_in_range() {
+        let x = [1, 2, 3, 4, 5];
+        let mut rb = RingBuffer::new(&x).unwrap();
+        assert_eq!(rb.int_in_range(0u32, 11).unwrap(), 5);
+        assert_eq!(rb.int_in_range(0u32, 11).unwrap(), 4);
+        assert_eq!(rb.int_in_range(0u32, 11).unwrap(), 3);
+    }
+
+    #[test]
+    fn ring_buffer_peek_bytes() {
+        let x = [1, 2, 3, 4];
+        let rb = RingBuffer::new(&x).unwrap();
+        assert_eq!(rb.peek_bytes(2), Some(&[1, 2][..]));
+        assert_eq!(rb.peek_bytes(4), Some(&[1, 2, 3, 4][..]));
+        assert_eq!(rb.peek_bytes(5), None);
+    }
+
+    #[test]
+    fn ring_buffer_take_bytes() {
+        let x = [1, 2, 3, 4];
+        let mut rb = RingBuffer::new(&x).unwrap();
+        assert_eq!(rb.take_bytes(2).unwrap(), &[1, 2]);
+        assert_eq!(rb.take_bytes(2).unwrap(), &[3, 4]);
+        assert!(rb.take_bytes(5).is_err());
+    }
+
+    #[test]
+    fn ring_buffer_checkpoint_restore() {
+        let x = [1, 2, 3, 4];
+        let mut rb = RingBuffer::new(&x).unwrap();
+        let cp = rb.checkpoint();
+        let mut z = [0; 4];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+        rb.restore(cp);
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn ring_buffer_set_virtual_len() {
+        let x = [1, 2, 3, 4];
+        let mut rb = RingBuffer::new(&x).unwrap();
+        assert_eq!(2, rb.shrink());
+        rb.set_virtual_len(4);
+        let mut z = [0; 4];
+        rb.fill_buffer(&mut z).unwrap();
+        assert_eq!(z, [1, 2, 3, 4]);
+    }
 }